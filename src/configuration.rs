@@ -0,0 +1,95 @@
+/// The configuration of a sandboxed process, specifying the resource limits
+/// that the sandbox should enforce.
+///
+/// Build one with [`SandboxConfiguration::build`].
+#[derive(Clone, Debug, Default)]
+pub struct SandboxConfiguration {
+    pub(crate) memory_limit: Option<u64>,
+    pub(crate) time_limit: Option<u64>,
+    pub(crate) wall_time_limit: Option<u64>,
+    pub(crate) process_limit: Option<u64>,
+    pub(crate) open_files_limit: Option<u64>,
+    pub(crate) file_size_limit: Option<u64>,
+    pub(crate) stack_limit: Option<u64>,
+    pub(crate) track_descendants: bool,
+}
+
+impl SandboxConfiguration {
+    /// Starts building a new configuration using the builder pattern.
+    pub fn build() -> SandboxConfigurationBuilder {
+        SandboxConfigurationBuilder(SandboxConfiguration::default())
+    }
+}
+
+/// Builder for [`SandboxConfiguration`].
+pub struct SandboxConfigurationBuilder(SandboxConfiguration);
+
+impl SandboxConfigurationBuilder {
+    /// Sets the maximum amount of memory (in bytes) the process can allocate.
+    pub fn memory_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.memory_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum amount of CPU time (in seconds) the process can use.
+    pub fn time_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.time_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum wall clock time (in seconds) the process is allowed
+    /// to run for, regardless of whether it is actually consuming CPU time.
+    ///
+    /// This is enforced on top of (and independently from) `time_limit`, and
+    /// requires the `pidfd_open(2)` syscall to be available; on kernels that
+    /// lack it the limit is not enforced.
+    pub fn wall_time_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.wall_time_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of processes/threads the process (and its
+    /// descendants) can create, guarding against fork bombs.
+    pub fn process_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.process_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of file descriptors the process can have open
+    /// at once.
+    pub fn open_files_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.open_files_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of any file the process writes,
+    /// guarding against filling up the disk.
+    pub fn file_size_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.file_size_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of the process' stack.
+    pub fn stack_limit(&mut self, limit: u64) -> &mut Self {
+        self.0.stack_limit = Some(limit);
+        self
+    }
+
+    /// If set, the resource usage reported by [`crate::wait`] also accounts
+    /// for the whole descendant process tree, not just the direct child:
+    /// the maximum RSS across all descendants and their summed CPU times are
+    /// folded into the returned usage. Only descendants that were reaped by
+    /// their parents are seen, and a descendant's peak RSS is only counted
+    /// when it exceeds the largest peak RSS of any child this process reaped
+    /// before the run. Leave unset (the default) for the cheaper
+    /// single-process behavior.
+    pub fn track_descendants(&mut self, value: bool) -> &mut Self {
+        self.0.track_descendants = value;
+        self
+    }
+
+    /// Builds the [`SandboxConfiguration`].
+    pub fn build(&self) -> SandboxConfiguration {
+        self.0.clone()
+    }
+}
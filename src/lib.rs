@@ -0,0 +1,10 @@
+mod configuration;
+mod result;
+mod util;
+
+pub use configuration::{SandboxConfiguration, SandboxConfigurationBuilder};
+pub use result::{ExitStatus, ResourceUsage};
+pub use util::{setup_resource_limits, wait, DescendantUsage};
+
+/// The result type used throughout this crate.
+pub type Result<T> = std::result::Result<T, failure::Error>;
@@ -0,0 +1,23 @@
+/// The exit status of a sandboxed process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The process terminated normally with the given exit code.
+    ExitCode(i32),
+    /// The process was killed by the given signal.
+    Signal(i32),
+}
+
+/// The resource usage of a sandboxed process, as measured by the sandbox.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// The maximum amount of memory used, in bytes.
+    pub memory_usage: u64,
+    /// The number of seconds spent in user mode.
+    pub user_cpu_time: f64,
+    /// The number of seconds spent in kernel mode.
+    pub system_cpu_time: f64,
+    /// The wall clock time elapsed while the process was running, in seconds.
+    pub wall_time_usage: f64,
+    /// Whether the process was killed because it exceeded `wall_time_limit`.
+    pub killed_by_wall_time_limit: bool,
+}
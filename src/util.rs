@@ -1,6 +1,7 @@
 use crate::configuration::SandboxConfiguration;
 use crate::result::{ExitStatus, ResourceUsage};
 use crate::Result;
+use std::time::{Duration, Instant};
 
 // MacOS libc crate seems to have miss this function... so I declare it
 extern "C" {
@@ -22,6 +23,22 @@ pub fn setup_resource_limits(config: &SandboxConfiguration) -> Result<()> {
         set_resource_limit(libc::RLIMIT_CPU, time_limit)?;
     }
 
+    if let Some(process_limit) = config.process_limit {
+        set_resource_limit(libc::RLIMIT_NPROC, process_limit)?;
+    }
+
+    if let Some(open_files_limit) = config.open_files_limit {
+        set_resource_limit(libc::RLIMIT_NOFILE, open_files_limit)?;
+    }
+
+    if let Some(file_size_limit) = config.file_size_limit {
+        set_resource_limit(libc::RLIMIT_FSIZE, file_size_limit)?;
+    }
+
+    if let Some(stack_limit) = config.stack_limit {
+        set_resource_limit(libc::RLIMIT_STACK, stack_limit)?;
+    }
+
     // No core dumps
     set_resource_limit(libc::RLIMIT_CORE, 0)
 }
@@ -46,14 +63,117 @@ fn set_resource_limit(resource: Resource, limit: u64) -> Result<()> {
     }
 }
 
-/// Wait for child completion, returning a WaitStatus and ResourceUsage
-pub fn wait(pid: libc::pid_t) -> Result<(ExitStatus, ResourceUsage)> {
+/// Wait for child completion, returning an ExitStatus and ResourceUsage.
+///
+/// If `config.wall_time_limit` is set and the kernel supports `pidfd_open(2)`
+/// (Linux >= 5.3), the child is watched through a pidfd with `poll`, so that
+/// the wait can be interrupted as soon as the wall clock deadline expires,
+/// killing the whole process group with `SIGKILL`. On kernels lacking pidfd
+/// support (or on non-Linux targets), this transparently falls back to the
+/// blocking `wait4` below, in which case `wall_time_limit` is not enforced.
+///
+/// `start_time` must be taken immediately before the child is spawned, so
+/// that the computed `wall_time_usage` also accounts for the time spent
+/// between spawning the child and calling this function.
+///
+/// If `config.track_descendants` is set, `descendants_baseline` must be
+/// `Some`, captured (via [`DescendantUsage::snapshot`]) at that same point,
+/// so that only usage accrued by this child's own descendants is counted;
+/// it is an error to set `track_descendants` without providing one. When
+/// `track_descendants` is unset, `descendants_baseline` is ignored and no
+/// extra `getrusage` call is made, keeping single-process setups cheap.
+pub fn wait(
+    pid: libc::pid_t,
+    start_time: Instant,
+    descendants_baseline: Option<DescendantUsage>,
+    config: &SandboxConfiguration,
+) -> Result<(ExitStatus, ResourceUsage)> {
+    if config.track_descendants && descendants_baseline.is_none() {
+        return Err(failure::err_msg(
+            "track_descendants is set but no descendants_baseline was provided",
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(wall_time_limit) = config.wall_time_limit {
+            if let Some(fd) = pidfd::open(pid) {
+                return wait_with_pidfd(
+                    pid,
+                    fd,
+                    wall_time_limit,
+                    start_time,
+                    descendants_baseline,
+                    config,
+                );
+            }
+        }
+    }
+    wait_blocking(pid, start_time, descendants_baseline, config)
+}
+
+/// Waits for `pid` through a pidfd, enforcing `wall_time_limit` (in seconds).
+/// Reaps the child with `wait4` exactly as the blocking path does, whether it
+/// exited on its own or was killed for exceeding the deadline.
+#[cfg(target_os = "linux")]
+fn wait_with_pidfd(
+    pid: libc::pid_t,
+    fd: libc::c_int,
+    wall_time_limit: u64,
+    start_time: Instant,
+    descendants_baseline: Option<DescendantUsage>,
+    config: &SandboxConfiguration,
+) -> Result<(ExitStatus, ResourceUsage)> {
+    let deadline = start_time + Duration::from_secs(wall_time_limit);
+    let ready = pidfd::wait_ready(fd, deadline);
+    unsafe { libc::close(fd) };
+
+    let ready = match ready {
+        Ok(ready) => ready,
+        Err(error) => {
+            // poll() itself failed, so we can no longer tell whether `pid`
+            // exceeded its deadline. Kill and reap it before propagating the
+            // error, rather than abandoning it to run or zombify forever.
+            unsafe { libc::kill(-pid, libc::SIGKILL) };
+            let _ = wait_blocking(pid, start_time, descendants_baseline, config);
+            return Err(error);
+        }
+    };
+
+    if !ready {
+        // Wall time exceeded: kill the whole process group so that any
+        // children spawned by `pid` are cleaned up too, then reap below.
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+
+    let (status, mut resource_usage) =
+        wait_blocking(pid, start_time, descendants_baseline, config)?;
+    let status = if ready {
+        status
+    } else {
+        ExitStatus::Signal(libc::SIGKILL)
+    };
+    resource_usage.killed_by_wall_time_limit = !ready;
+
+    Ok((status, resource_usage))
+}
+
+/// Waits for `pid` completion with a blocking `wait4`, returning its
+/// ExitStatus and ResourceUsage. This is the fallback used whenever pidfd is
+/// unavailable, and the final reaping step of the pidfd-based wait.
+fn wait_blocking(
+    pid: libc::pid_t,
+    start_time: Instant,
+    descendants_baseline: Option<DescendantUsage>,
+    config: &SandboxConfiguration,
+) -> Result<(ExitStatus, ResourceUsage)> {
     let mut status = 0;
     let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
 
     if unsafe { wait4(pid, &mut status, 0, &mut rusage) } != pid {
         return Err(failure::err_msg("Error waiting for child completion"));
     };
+    let wall_time_usage = start_time.elapsed().as_secs_f64();
 
     let status = unsafe {
         if libc::WIFEXITED(status) {
@@ -65,17 +185,164 @@ pub fn wait(pid: libc::pid_t) -> Result<(ExitStatus, ResourceUsage)> {
         }
     };
 
-    let resource_usage = ResourceUsage {
+    let mut resource_usage = ResourceUsage {
         memory_usage: rusage.ru_maxrss as u64 * 1024,
         user_cpu_time: rusage.ru_utime.tv_usec as f64 / 1_000_000.0 + rusage.ru_utime.tv_sec as f64,
         system_cpu_time: rusage.ru_stime.tv_usec as f64 / 1_000_000.0
             + rusage.ru_stime.tv_sec as f64,
-        wall_time_usage: 0.0,
+        wall_time_usage,
+        killed_by_wall_time_limit: false,
     };
 
+    if config.track_descendants {
+        // `wait` already rejects `track_descendants` without a baseline.
+        let baseline = descendants_baseline.expect("descendants_baseline validated by wait()");
+        fold_descendant_usage(&mut resource_usage, baseline)?;
+    }
+
     Ok((status, resource_usage))
 }
 
+/// A point-in-time snapshot of this process' cumulative resource usage over
+/// its terminated and reaped children, i.e. `getrusage(2)` with
+/// `RUSAGE_CHILDREN`.
+///
+/// `RUSAGE_CHILDREN` accumulates over the *whole lifetime of the calling
+/// process*, not just the child currently being sandboxed, so a snapshot
+/// must be taken immediately before spawning that child and passed to
+/// [`wait`] so that only usage accrued since then is attributed to it.
+///
+/// `RUSAGE_CHILDREN` is also a single counter shared by the whole process:
+/// if two sandbox runs with `config.track_descendants` set overlap in the
+/// same process (one's `snapshot` → spawn → [`wait`] sequence interleaves
+/// with another's), each run's baseline/current diff can pick up CPU time
+/// and RSS contributed by the *other* run's descendants. Concurrent
+/// in-process sandbox runs are therefore unsupported with this flag; only
+/// use `track_descendants` when runs in the same process are sequential.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DescendantUsage {
+    memory_usage: u64,
+    user_cpu_time: f64,
+    system_cpu_time: f64,
+}
+
+impl DescendantUsage {
+    /// Captures the current cumulative resource usage of this process'
+    /// terminated descendants. Call this immediately before spawning the
+    /// child that will later be passed to [`wait`].
+    pub fn snapshot() -> Result<Self> {
+        let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+        if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut rusage) } < 0 {
+            return Err(failure::err_msg("Error calling getrusage()"));
+        }
+        Ok(DescendantUsage {
+            memory_usage: rusage.ru_maxrss as u64 * 1024,
+            user_cpu_time: rusage.ru_utime.tv_usec as f64 / 1_000_000.0
+                + rusage.ru_utime.tv_sec as f64,
+            system_cpu_time: rusage.ru_stime.tv_usec as f64 / 1_000_000.0
+                + rusage.ru_stime.tv_sec as f64,
+        })
+    }
+}
+
+/// Folds the resource usage of `pid`'s terminated descendants (grandchildren
+/// and beyond, already reaped by the time `pid` itself exited) into
+/// `resource_usage`, relative to `baseline`.
+///
+/// CPU times are cumulative, so the amount accrued since `baseline` is their
+/// difference; but that delta already includes `pid`'s own CPU time (reaping
+/// it via `wait4` just above folds it into our `RUSAGE_CHILDREN`), which is
+/// already present in `resource_usage`, so only the remainder beyond that is
+/// added to avoid double-counting it.
+///
+/// `ru_maxrss` is a high-water mark that the kernel never resets, so it
+/// cannot be subtracted the same way: a rise above `baseline` *is* the real
+/// peak reached during this run, not a delta to compute further; anything
+/// at or below `baseline` tells us nothing about this run, so it is
+/// conservatively reported as unknown (0).
+fn fold_descendant_usage(
+    resource_usage: &mut ResourceUsage,
+    baseline: DescendantUsage,
+) -> Result<()> {
+    let current = DescendantUsage::snapshot()?;
+
+    let children_memory_usage = if current.memory_usage > baseline.memory_usage {
+        current.memory_usage
+    } else {
+        0
+    };
+    let children_user_cpu_time = (current.user_cpu_time
+        - baseline.user_cpu_time
+        - resource_usage.user_cpu_time)
+        .max(0.0);
+    let children_system_cpu_time = (current.system_cpu_time
+        - baseline.system_cpu_time
+        - resource_usage.system_cpu_time)
+        .max(0.0);
+
+    resource_usage.memory_usage = resource_usage.memory_usage.max(children_memory_usage);
+    resource_usage.user_cpu_time += children_user_cpu_time;
+    resource_usage.system_cpu_time += children_system_cpu_time;
+
+    Ok(())
+}
+
+/// Minimal wrapper around the `pidfd_open(2)` syscall and the `poll`-based
+/// wait on the resulting file descriptor. Kept separate so that the
+/// availability detection and `EINTR` handling are easy to reason about in
+/// isolation.
+#[cfg(target_os = "linux")]
+mod pidfd {
+    use crate::Result;
+    use std::time::Instant;
+
+    /// Opens a pidfd for `pid`, or returns `None` if the kernel does not
+    /// support `pidfd_open` (requires Linux >= 5.3). The pidfd is opened
+    /// directly from the pid handed to us by `fork`/`clone`, so it is
+    /// race-free: the child cannot have been reaped by anyone else yet.
+    pub fn open(pid: libc::pid_t) -> Option<libc::c_int> {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+        if fd < 0 {
+            None
+        } else {
+            Some(fd as libc::c_int)
+        }
+    }
+
+    /// Polls `fd` until it becomes readable (the process has terminated) or
+    /// `deadline` elapses, whichever happens first, returning whether it
+    /// became ready. `EINTR` is handled by recomputing the remaining timeout
+    /// against `deadline` and retrying; any other `poll` failure is a
+    /// sandbox-internal error and is propagated rather than treated as a
+    /// timeout.
+    pub fn wait_ready(fd: libc::c_int, deadline: Instant) -> Result<bool> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            let timeout_ms = remaining.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+            let mut poll_fd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            match unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) } {
+                ret if ret > 0 => return Ok(true),
+                0 => return Ok(false),
+                _ => {
+                    let error = std::io::Error::last_os_error();
+                    if error.kind() != std::io::ErrorKind::Interrupted {
+                        return Err(failure::err_msg(format!("Error calling poll(): {}", error)));
+                    }
+                    // EINTR: loop around and recompute the remaining timeout.
+                }
+            }
+        }
+    }
+}
+
 #[cfg(unix)]
 mod unix {
     use std::os::raw::c_char;